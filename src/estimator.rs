@@ -0,0 +1,113 @@
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+const DEFAULT_BATCHES: usize = 20;
+const CONFIDENCE_LEVEL: f64 = 0.975;
+
+// A two-sided interval around a point estimate, reported at the 95%
+// confidence level.
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// Accumulates per-trial system-failure times and reports reliability-
+// engineering metrics from them, instead of callers having to compute
+// everything themselves from a raw list of times.
+//
+// Every sample carries a likelihood-ratio weight so the same estimator works
+// for both crude Monte Carlo (weight 1.0 via `add_sample`) and importance
+// sampling (`add_weighted_sample`): metrics are computed as plain averages
+// of `weight * value` over all N trials, which stays an unbiased estimator
+// under either scheme.
+//
+// Confidence intervals use the batch-means method: the N weighted values
+// are partitioned into `batches` equal-size batches, each batch's mean is
+// treated as one (approximately normal) observation, and the interval is
+// formed from the t-distribution of those batch means:
+// grand_mean +/- t_{k-1,0.975} * s_batch / sqrt(k).
+pub struct Estimator {
+    times: Vec<f64>,
+    weights: Vec<f64>,
+    batches: usize,
+}
+
+impl Estimator {
+    pub fn new() -> Estimator {
+        Estimator::with_batches(DEFAULT_BATCHES)
+    }
+    pub fn with_batches(batches: usize) -> Estimator {
+        Estimator{times: Vec::new(), weights: Vec::new(), batches}
+    }
+    pub fn add_sample(&mut self, time: f64) {
+        self.add_weighted_sample(time, 1.0);
+    }
+    // `weight` is the trial's likelihood ratio L = prod(f_orig(t_i) / f_biased(t_i))
+    // over every basic event sampled under a biased distribution during the
+    // trial; pass 1.0 for crude (unbiased) sampling.
+    pub fn add_weighted_sample(&mut self, time: f64, weight: f64) {
+        self.times.push(time);
+        self.weights.push(weight);
+    }
+    // Empirical unreliability F(t) = P(system failed before t).
+    pub fn unreliability_at(&self, t: f64) -> f64 {
+        let indicators: Vec<f64> = self.times.iter().zip(&self.weights)
+            .map(|(&time, &weight)| if time <= t { weight } else { 0.0 })
+            .collect();
+        indicators.iter().sum::<f64>() / indicators.len() as f64
+    }
+    // Point availability at `t`: the complement of unreliability, since a
+    // trial in this engine ends at the system's first failure rather than
+    // continuing to track uptime/downtime afterward.
+    pub fn availability_at(&self, t: f64) -> f64 {
+        1.0 - self.unreliability_at(t)
+    }
+    pub fn mttf(&self) -> f64 {
+        let weighted_times = self.weighted_times();
+        weighted_times.iter().sum::<f64>() / weighted_times.len() as f64
+    }
+    pub fn standard_error(&self) -> f64 {
+        batch_stats(&self.weighted_times(), self.batches).1
+    }
+    // Needs at least 2 batches (and thus at least 2 samples) to fit a
+    // t-distribution over the batch means; with only 1 batch, batch-means
+    // variance is undefined, so that case is reported as an error instead of
+    // panicking on it.
+    pub fn confidence_interval(&self) -> Result<ConfidenceInterval, String> {
+        let (grand_mean, standard_error, k) = batch_stats(&self.weighted_times(), self.batches);
+        if k < 2 {
+            return Err(format!("confidence_interval needs at least 2 batches, got {} (add more samples)", k));
+        }
+        let t_dist = StudentsT::new(0.0, 1.0, (k - 1) as f64).expect("need at least 2 batches");
+        let margin = t_dist.inverse_cdf(CONFIDENCE_LEVEL) * standard_error;
+        Ok(ConfidenceInterval{lower: grand_mean - margin, upper: grand_mean + margin})
+    }
+    fn weighted_times(&self) -> Vec<f64> {
+        self.times.iter().zip(&self.weights).map(|(time, weight)| time * weight).collect()
+    }
+}
+
+// Returns (grand mean of batch means, standard error of that mean, batch count).
+fn batch_stats(values: &[f64], batches: usize) -> (f64, f64, usize) {
+    let means = batch_means(values, batches);
+    let k = means.len();
+    let grand_mean = means.iter().sum::<f64>() / k as f64;
+    let variance = means.iter().map(|mean| (mean - grand_mean).powi(2)).sum::<f64>() / (k as f64 - 1.0);
+    let standard_error = variance.sqrt() / (k as f64).sqrt();
+    (grand_mean, standard_error, k)
+}
+
+// Splits `values` into `batches` equal-size batches, dropping any remainder
+// so every batch carries the same weight. `batches` is clamped to the number
+// of samples available, so a caller that hasn't added as many samples yet as
+// its configured batch count gets one sample per batch instead of a panic.
+fn batch_means(values: &[f64], batches: usize) -> Vec<f64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let batches = batches.min(values.len()).max(1);
+    let batch_size = values.len() / batches;
+    values.chunks(batch_size)
+        .take(batches)
+        .map(|batch| batch.iter().sum::<f64>() / batch.len() as f64)
+        .collect()
+}
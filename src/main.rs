@@ -1,11 +1,15 @@
 use std::{any::{Any, TypeId}, ops::Deref, ptr::null, rc::Rc};
-use std::fs::File;
-use std::io::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::path::Path;
 
 use rand::prelude::{Distribution, ThreadRng, thread_rng};
 use statrs::distribution::{Exp, Gamma, Weibull};
 
+mod estimator;
+use estimator::Estimator;
+mod parser;
+
 const STATUS_ALIVE: usize = 0;
 const STATUS_DEAD: usize = 1;
 const STATUS_DYNAMIC: usize = 2;
@@ -35,19 +39,88 @@ trait FTElement {
     fn get_type(&self) -> usize;
     fn set_status(&mut self, status: usize);
     fn as_any(&self) -> &dyn Any;
+    // Dynamic gates (FT_SEQUENTIAL) carry state that can't be inferred from
+    // their children, so they need to react to every event in the trial
+    // rather than just recompute from the current status of their children.
+    // `initialize` (re)establishes that state after a reset, and `update` is
+    // invoked by `FT::process_event_time` for every event in the trial so a
+    // gate can claim/release spares, check orderings, etc. Static gates and
+    // basic events use the default no-op implementations.
+    fn initialize(&mut self, _ft: &mut FT) {}
+    fn update(&mut self, _ft: &mut FT, _event_type: usize, _source: usize) {}
+}
+
+// Placeholder used only to temporarily take a dynamic gate out of
+// `FT::elements` so it can be handed a `&mut FT` (itself included) without
+// upsetting the borrow checker. It is swapped back in immediately and should
+// never be observed by any real logic.
+struct Placeholder {
+    id: usize,
+}
+
+impl FTElement for Placeholder {
+    fn get_failed(&self, _ft: &FT) -> bool {
+        false
+    }
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn get_type(&self) -> usize {
+        FT_SEQUENTIAL
+    }
+    fn set_status(&mut self, _status: usize) {}
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct FT {
     root: usize,
     elements: Vec<Box<dyn FTElement>>,
+    // Spares shared between multiple GateSpare parents must not be
+    // double-claimed, so ownership is tracked centrally here rather than
+    // duplicated per gate: spare_claims[child] is the id of the GateSpare
+    // currently using that child, if any.
+    spare_claims: Vec<Option<usize>>,
+    // Last time each element transitioned to STATUS_DEAD, cleared on repair.
+    // GatePAND uses this to check its children failed in order.
+    last_failure_time: Vec<Option<f64>>,
+    // Original element name from the model file, if the tree was built by
+    // `parser::parse` rather than hand-assembled (e.g. by the build_* demo
+    // functions), so callers can
+    // render ids (e.g. minimal cut sets) back into the names a model author
+    // wrote instead of opaque integers.
+    names: Vec<Option<String>>,
+    current_time: f64,
 }
 
 impl FT {
     fn new() -> FT {
-        FT{root: 0, elements: Vec::new()}
+        FT{root: 0, elements: Vec::new(), spare_claims: Vec::new(), last_failure_time: Vec::new(), names: Vec::new(), current_time: 0.0}
+    }
+    // Builds a tree from a declarative JSON model file instead of hand-coding
+    // it in main(), so a new model doesn't require recompiling. See the
+    // `parser` module for the schema.
+    fn from_file(path: &Path) -> Result<FT, String> {
+        parser::parse(path)
     }
     fn add_element(&mut self, element: Box<dyn FTElement>) {
-        self.elements.insert(element.get_id(), element)
+        let id = element.get_id();
+        if id >= self.spare_claims.len() {
+            self.spare_claims.resize(id + 1, None);
+            self.last_failure_time.resize(id + 1, None);
+            self.names.resize(id + 1, None);
+        }
+        self.elements.insert(id, element)
+    }
+    fn set_name(&mut self, element: usize, name: String) {
+        self.names[element] = Some(name);
+    }
+    // Falls back to the raw id as a string for trees without names (e.g.
+    // `build_two_of_three`'s hand-assembled tree), so callers can render an
+    // id uniformly regardless of how the tree was built.
+    fn name_of(&self, element: usize) -> String {
+        self.names.get(element).cloned().flatten().unwrap_or_else(|| element.to_string())
     }
     fn get_failed(&self, element: usize) -> bool {
         self.elements.get(element)
@@ -63,44 +136,194 @@ impl FT {
         }
         basic_events
     }
-    fn sample_failure(&self, element: usize, r: &mut ThreadRng) -> Result<f64, &'static str> {
+    fn sample_repair(&self, element: usize, r: &mut ThreadRng) -> Result<f64, &'static str> {
         let element = self.elements.get(element).unwrap();
         if element.get_type() == FT_BASIC {
             let element = element.as_any().downcast_ref::<BasicEvent>().expect("Not a basic event");
-            Ok(element.sample_failure(r))
+            Ok(element.sample_repair(r))
         } else {
             Err("Not a basic event")
         }
     }
-    fn sample_repair(&self, element: usize, r: &mut ThreadRng) -> Result<f64, &'static str> {
+    // Samples a failure time under `scheme`, also returning the likelihood
+    // ratio of that sample (1.0 under crude sampling) so `run_trial` can
+    // accumulate a trial weight for importance sampling.
+    fn sample_failure_weighted(&self, element: usize, r: &mut ThreadRng, scheme: SamplingScheme) -> Result<(f64, f64), &'static str> {
         let element = self.elements.get(element).unwrap();
         if element.get_type() == FT_BASIC {
             let element = element.as_any().downcast_ref::<BasicEvent>().expect("Not a basic event");
-            Ok(element.sample_repair(r))
+            match scheme {
+                SamplingScheme::Crude => Ok((element.sample_failure(r), 1.0)),
+                SamplingScheme::ImportanceSampling{bias_factor} => Ok(element.sample_failure_biased(r, bias_factor)),
+            }
         } else {
             Err("Not a basic event")
         }
     }
+    // Central bookkeeping for hot-spare pools: a child can only be claimed by
+    // one GateSpare at a time.
+    fn claim_spare(&mut self, child: usize, owner: usize) -> bool {
+        if self.spare_claims[child].is_none() {
+            self.spare_claims[child] = Some(owner);
+            true
+        } else {
+            false
+        }
+    }
+    fn release_spare(&mut self, child: usize, owner: usize) {
+        if self.spare_claims[child] == Some(owner) {
+            self.spare_claims[child] = None;
+        }
+    }
+    fn last_failure_time(&self, element: usize) -> Option<f64> {
+        self.last_failure_time[element]
+    }
+    // Used by GateFDEP to force a dependent basic event dead without it
+    // going through its own sampled failure distribution. Goes through the
+    // same notify_dynamic_gates path as a real event so other dynamic gates
+    // sharing the dependent (a GateSpare pool member, a GatePAND/GateSEQ
+    // child, ...) see the forced death too.
+    fn force_dead(&mut self, element: usize) {
+        self.elements.get_mut(element).unwrap().set_status(STATUS_DEAD);
+        self.last_failure_time[element] = Some(self.current_time);
+        self.notify_dynamic_gates(element, EVENT_FAILURE);
+    }
     fn process_event_time(&mut self, event_time: EventTime) {
+        self.current_time = event_time.time;
         match event_time.event_type {
             EVENT_FAILURE => {
                 self.elements.get_mut(event_time.element).unwrap().set_status(STATUS_DEAD);
+                self.last_failure_time[event_time.element] = Some(event_time.time);
             }
             EVENT_REPAIR => {
                 self.elements.get_mut(event_time.element).unwrap().set_status(STATUS_ALIVE);
-                //update parent elements if they are sequential
+                self.last_failure_time[event_time.element] = None;
             }
             _ => {
                 panic!("Unknown event type")
             }
         };
+        self.notify_dynamic_gates(event_time.element, event_time.event_type);
     }
-    fn reset_basic_events(&mut self) {
-        let basic_events = self.get_basic_events();
-        for i in basic_events {
-            self.elements.get_mut(i).unwrap().set_status(STATUS_ALIVE);
-        };
+    // Every dynamic gate gets a chance to react to every event, since a
+    // GateSpare's pool or a GatePAND's ordering can be affected by events on
+    // elements they don't otherwise reference as a direct parent/child.
+    fn notify_dynamic_gates(&mut self, source: usize, event_type: usize) {
+        for i in 0..self.elements.len() {
+            if self.elements[i].get_type() == FT_SEQUENTIAL {
+                let mut gate = std::mem::replace(&mut self.elements[i], Box::new(Placeholder{id: i}));
+                gate.update(self, event_type, source);
+                self.elements[i] = gate;
+            }
+        }
+    }
+    fn reset_elements(&mut self) {
+        for i in 0..self.elements.len() {
+            match self.elements[i].get_type() {
+                FT_BASIC => self.elements[i].set_status(STATUS_ALIVE),
+                FT_SEQUENTIAL => self.elements[i].set_status(STATUS_DYNAMIC),
+                _ => {}
+            }
+        }
+        self.spare_claims.iter_mut().for_each(|c| *c = None);
+        self.last_failure_time.iter_mut().for_each(|t| *t = None);
+        self.current_time = 0.0;
+        for i in 0..self.elements.len() {
+            if self.elements[i].get_type() == FT_SEQUENTIAL {
+                let mut gate = std::mem::replace(&mut self.elements[i], Box::new(Placeholder{id: i}));
+                gate.initialize(self);
+                self.elements[i] = gate;
+            }
+        }
+    }
+    // Minimal cut sets of the static (AND/OR/VOTE) gate structure, found by
+    // MOCUS top-down expansion from the root: a working set containing an
+    // AND gate expands in place into the cartesian combination of its
+    // children (same cut set, since every child must fail too), an OR gate
+    // splits into one cut set per child, and a k-of-n VOTE gate expands
+    // into every size-k combination of its children. Expansion stops once
+    // every element in a working set is a basic event, and the result is
+    // pruned to the sets that aren't a superset of some other set. This is
+    // a purely structural analysis - it does not touch the dynamic gates or
+    // distributions that drive the simulation, so a dynamic gate reachable
+    // from the root is reported as an error rather than expanded.
+    fn minimal_cut_sets(&self) -> Result<Vec<Vec<usize>>, String> {
+        let mut worklist: Vec<Vec<usize>> = vec![vec![self.root]];
+        let mut expanded_sets: Vec<Vec<usize>> = Vec::new();
+
+        while let Some(mut cut_set) = worklist.pop() {
+            match cut_set.iter().position(|&id| self.elements[id].get_type() != FT_BASIC) {
+                None => {
+                    cut_set.sort();
+                    cut_set.dedup();
+                    expanded_sets.push(cut_set);
+                }
+                Some(index) => {
+                    let gate_id = cut_set.remove(index);
+                    let gate = self.elements[gate_id].as_any();
+                    if let Some(and_gate) = gate.downcast_ref::<GateAnd>() {
+                        let mut next = cut_set;
+                        next.extend(and_gate.children.get());
+                        worklist.push(next);
+                    } else if let Some(or_gate) = gate.downcast_ref::<GateOr>() {
+                        for &child in or_gate.children.get() {
+                            let mut next = cut_set.clone();
+                            next.push(child);
+                            worklist.push(next);
+                        }
+                    } else if let Some(vote_gate) = gate.downcast_ref::<GateVote>() {
+                        let children = vote_gate.children.get();
+                        for combination in combinations(children, vote_gate.threshold) {
+                            let mut next = cut_set.clone();
+                            next.extend(combination);
+                            worklist.push(next);
+                        }
+                    } else {
+                        return Err(format!(
+                            "minimal_cut_sets only supports the static AND/OR/VOTE gate structure, \
+                             but element {} is a dynamic gate", gate_id));
+                    }
+                }
+            }
+        }
+
+        Ok(prune_non_minimal(expanded_sets))
+    }
+}
+
+// All size-`k` combinations of `items`, preserving their relative order.
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if items.len() < k {
+        return Vec::new();
+    }
+    let mut result = Vec::new();
+    for i in 0..=(items.len() - k) {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, items[i]);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+// Drops any cut set that is a superset of another, leaving only the minimal
+// ones. Sets are assumed already sorted and deduplicated internally.
+fn prune_non_minimal(mut cut_sets: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    cut_sets.sort_by_key(|set| set.len());
+    cut_sets.dedup();
+    let mut minimal: Vec<Vec<usize>> = Vec::new();
+    'candidates: for candidate in cut_sets {
+        for kept in &minimal {
+            if kept.iter().all(|id| candidate.contains(id)) {
+                continue 'candidates;
+            }
+        }
+        minimal.push(candidate);
     }
+    minimal
 }
 
 pub struct BasicEvent {
@@ -133,7 +356,7 @@ impl FTElement for BasicEvent {
 }
 
 impl BasicEvent {
-    fn new(id: usize, failure_distribution: usize, failure_scale: f64, failure_shape: f64, 
+    fn new(id: usize, failure_distribution: usize, failure_scale: f64, failure_shape: f64,
             repair_distribution: usize, repair_scale: f64, repair_shape: f64) -> BasicEvent{
         let failure_distribution: DistributionType = match failure_distribution {
             DIST_EXP => {
@@ -173,6 +396,24 @@ impl BasicEvent {
             _ => panic!("Cannot sample a none distribution"),
         }
     }
+    // Samples under a rate inflated by `bias_factor`, returning the sample
+    // alongside its likelihood ratio f_orig(t)/f_biased(t) so the caller can
+    // correct for the bias. Only exponential failure distributions are
+    // tilted; other distributions fall back to crude sampling with a
+    // likelihood ratio of 1.0.
+    fn sample_failure_biased(&self, r: &mut ThreadRng, bias_factor: f64) -> (f64, f64) {
+        match self.failure_distribution {
+            DistributionType::Exp(d) => {
+                let original_rate = d.rate();
+                let biased_rate = original_rate * bias_factor;
+                let biased = Exp::new(biased_rate).unwrap();
+                let t = biased.sample(r);
+                let weight = (original_rate / biased_rate) * (-(original_rate - biased_rate) * t).exp();
+                (t, weight)
+            }
+            _ => (self.sample_failure(r), 1.0),
+        }
+    }
     fn sample_repair(&self, r: &mut ThreadRng) -> f64 {
         match self.repair_distribution {
             DistributionType::Exp(d) => d.sample(r),
@@ -197,6 +438,9 @@ impl Children {
     fn add(&mut self, child: &dyn FTElement) {
         self.children.push(child.get_id());
     }
+    fn add_id(&mut self, child: usize) {
+        self.children.push(child);
+    }
 }
 
 pub struct GateAnd {
@@ -269,25 +513,26 @@ impl FTElement for GateOr {
 
 pub struct GateVote {
     id: usize,
+    // Minimum number of failed children for the gate itself to fail.
+    threshold: usize,
     children: Children,
 }
 
 impl GateVote {
-    fn new(id: usize) -> GateVote {
-        GateVote{id, children: Children::new()}
+    fn new(id: usize, threshold: usize) -> GateVote {
+        GateVote{id, threshold, children: Children::new()}
     }
 }
 
 impl FTElement for GateVote {
     fn get_failed(&self, ft: &FT) -> bool {
-        let threshold: usize = self.children.get().len() / 2;
         let mut failed: usize = 0;
         for child in self.children.get() {
             if ft.get_failed(*child) == true {
                 failed += 1;
             }
         }
-        failed > threshold
+        failed >= self.threshold
     }
     fn get_id(&self) -> usize {
         self.id
@@ -303,6 +548,227 @@ impl FTElement for GateVote {
     }
 }
 
+// Hot-spare gate: `children` is an ordered priority list (primary first, then
+// spares), `switch` is the event that must be alive to swap a failed active
+// child out for the next available one. The gate only fails once the pool is
+// exhausted or the switch itself has failed.
+pub struct GateSpare {
+    id: usize,
+    status: usize,
+    children: Children,
+    switch: usize,
+    active: Option<usize>,
+}
+
+impl GateSpare {
+    fn new(id: usize, switch: usize) -> GateSpare {
+        GateSpare{id, status: STATUS_DYNAMIC, children: Children::new(), switch, active: None}
+    }
+    fn activate_next(&mut self, ft: &mut FT) {
+        if let Some(previous) = self.active.take() {
+            ft.release_spare(previous, self.id);
+        }
+        if ft.get_failed(self.switch) {
+            self.status = STATUS_DEAD;
+            return;
+        }
+        for &candidate in self.children.get() {
+            if !ft.get_failed(candidate) && ft.claim_spare(candidate, self.id) {
+                self.active = Some(candidate);
+                self.status = STATUS_DYNAMIC;
+                return;
+            }
+        }
+        self.status = STATUS_DEAD;
+    }
+}
+
+impl FTElement for GateSpare {
+    fn get_failed(&self, _ft: &FT) -> bool {
+        self.status == STATUS_DEAD
+    }
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn get_type(&self) -> usize {
+        FT_SEQUENTIAL
+    }
+    fn set_status(&mut self, status: usize) {
+        self.status = status;
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn initialize(&mut self, ft: &mut FT) {
+        self.active = None;
+        self.status = STATUS_DYNAMIC;
+        self.activate_next(ft);
+    }
+    fn update(&mut self, ft: &mut FT, event_type: usize, source: usize) {
+        if source == self.switch || self.status == STATUS_DEAD {
+            return;
+        }
+        if Some(source) == self.active && event_type == EVENT_FAILURE {
+            self.activate_next(ft);
+        }
+        // Repairs of pool members that aren't currently active, or of the
+        // switch, need no action: activate_next re-checks availability
+        // itself the next time a swap is actually required.
+    }
+}
+
+// Fails only if its children fail left-to-right in the given order; if a
+// later child fails before an earlier one, the gate never fails even once
+// every child is dead, since the required ordering was never observed.
+pub struct GatePAND {
+    id: usize,
+    status: usize,
+    children: Children,
+}
+
+impl GatePAND {
+    fn new(id: usize) -> GatePAND {
+        GatePAND{id, status: STATUS_DYNAMIC, children: Children::new()}
+    }
+}
+
+impl FTElement for GatePAND {
+    fn get_failed(&self, _ft: &FT) -> bool {
+        self.status == STATUS_DEAD
+    }
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn get_type(&self) -> usize {
+        FT_SEQUENTIAL
+    }
+    fn set_status(&mut self, status: usize) {
+        self.status = status;
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn update(&mut self, ft: &mut FT, event_type: usize, source: usize) {
+        if event_type != EVENT_FAILURE || self.status == STATUS_DEAD || !self.children.get().contains(&source) {
+            return;
+        }
+        let mut previous_time: Option<f64> = None;
+        for &child in self.children.get() {
+            match ft.last_failure_time(child) {
+                Some(time) => {
+                    if let Some(previous_time) = previous_time {
+                        if time < previous_time {
+                            return;
+                        }
+                    }
+                    previous_time = Some(time);
+                }
+                None => return,
+            }
+        }
+        self.status = STATUS_DEAD;
+    }
+}
+
+// Enforces an allowed failure ordering: children must fail in exactly the
+// given order. An out-of-turn failure permanently disqualifies the gate
+// (it can never reach STATUS_DEAD), rather than being retroactively
+// re-evaluated the way GatePAND is.
+pub struct GateSEQ {
+    id: usize,
+    status: usize,
+    children: Children,
+    next: usize,
+    violated: bool,
+}
+
+impl GateSEQ {
+    fn new(id: usize) -> GateSEQ {
+        GateSEQ{id, status: STATUS_DYNAMIC, children: Children::new(), next: 0, violated: false}
+    }
+}
+
+impl FTElement for GateSEQ {
+    fn get_failed(&self, _ft: &FT) -> bool {
+        self.status == STATUS_DEAD
+    }
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn get_type(&self) -> usize {
+        FT_SEQUENTIAL
+    }
+    fn set_status(&mut self, status: usize) {
+        self.status = status;
+        if status != STATUS_DEAD {
+            self.next = 0;
+            self.violated = false;
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn update(&mut self, _ft: &mut FT, event_type: usize, source: usize) {
+        if event_type != EVENT_FAILURE || self.violated || self.status == STATUS_DEAD {
+            return;
+        }
+        match self.children.get().get(self.next) {
+            Some(&expected) if expected == source => {
+                self.next += 1;
+                if self.next == self.children.get().len() {
+                    self.status = STATUS_DEAD;
+                }
+            }
+            Some(_) => {
+                if self.children.get().contains(&source) {
+                    self.violated = true;
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+// A trigger event that forces every dependent basic event to STATUS_DEAD as
+// soon as it fails, bypassing their own sampled failure distributions.
+pub struct GateFDEP {
+    id: usize,
+    trigger: usize,
+    dependents: Children,
+}
+
+impl GateFDEP {
+    fn new(id: usize, trigger: usize) -> GateFDEP {
+        GateFDEP{id, trigger, dependents: Children::new()}
+    }
+}
+
+impl FTElement for GateFDEP {
+    fn get_failed(&self, ft: &FT) -> bool {
+        ft.get_failed(self.trigger)
+    }
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn get_type(&self) -> usize {
+        FT_SEQUENTIAL
+    }
+    // FDEP has no status of its own - get_failed always delegates straight to
+    // the trigger, so reset_elements's blanket set_status(STATUS_DYNAMIC) on
+    // every dynamic gate is simply a no-op here.
+    fn set_status(&mut self, _status: usize) {}
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn update(&mut self, ft: &mut FT, event_type: usize, source: usize) {
+        if source == self.trigger && event_type == EVENT_FAILURE {
+            for &dependent in self.dependents.get() {
+                ft.force_dead(dependent);
+            }
+        }
+    }
+}
+
 pub struct IDGenerator {
     counter: usize,
 }
@@ -323,201 +789,245 @@ pub struct EventTime {
     event_type: usize,
 }
 
-fn main() {
-    let mut id_gen = IDGenerator::new();
-    let mut ft = FT::new();
-
-    // let c1: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    //  DIST_WEIBULL, 100.0, 1.5, 
-    //  DIST_NONE, 0.0, 0.0);
-    // let c2: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    // DIST_WEIBULL, 100.0, 1.5, 
-    //  DIST_NONE, 0.0, 0.0);
-    // let c3: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    // DIST_WEIBULL, 100.0, 1.5, 
-    //  DIST_NONE, 0.0, 0.0);
-
-    // let mut g2: GateVote = GateVote::new(id_gen.get_next());
-    // g2.children.add(&c1);
-    // g2.children.add(&c2);
-    // g2.children.add(&c3);
-
-    // ft.add_element(Box::new(c1));
-    // ft.add_element(Box::new(c2));
-    // ft.add_element(Box::new(c3));
-
-    // let root_id = g2.get_id();
-    // ft.add_element(Box::new(g2));
-
-    // let c1: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    //  DIST_EXP, 1.0/100.0, 0.0, 
-    //  DIST_EXP, 1.0/100.0, 0.0);
-    // let c2: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    // DIST_EXP, 1.0/100.0, 0.0, 
-    // DIST_EXP, 1.0/100.0, 0.0);
-
-    // let mut g1: GateAnd = GateAnd::new(id_gen.get_next());
-    // g1.children.add(&c1);
-    // g1.children.add(&c2);
-
-    // ft.add_element(Box::new(c1));
-    // ft.add_element(Box::new(c2));
-
-    // let root_id = g1.get_id();
-    // ft.add_element(Box::new(g1));
-
-    let c1: BasicEvent = BasicEvent::new(id_gen.get_next(),
-     DIST_EXP, 1.0/100.0, 0.0, 
-     DIST_EXP, 1.0/100.0, 0.0);
-    let c2: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    DIST_EXP, 1.0/100.0, 0.0, 
-    DIST_EXP, 1.0/100.0, 0.0);
-    let c3: BasicEvent = BasicEvent::new(id_gen.get_next(),
-     DIST_EXP, 1.0/100.0, 0.0, 
-     DIST_EXP, 1.0/100.0, 0.0);
-    let c4: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    DIST_EXP, 1.0/100.0, 0.0, 
-    DIST_EXP, 1.0/100.0, 0.0);
-    let c5: BasicEvent = BasicEvent::new(id_gen.get_next(),
-     DIST_EXP, 1.0/100.0, 0.0, 
-     DIST_EXP, 1.0/100.0, 0.0);
-    let c6: BasicEvent = BasicEvent::new(id_gen.get_next(),
-    DIST_EXP, 1.0/100.0, 0.0, 
-    DIST_EXP, 1.0/100.0, 0.0);
-
-    let mut g1: GateAnd = GateAnd::new(id_gen.get_next());
-    g1.children.add(&c1);
-    g1.children.add(&c2);
-    let mut g2: GateAnd = GateAnd::new(id_gen.get_next());
-    g2.children.add(&c3);
-    g2.children.add(&c4);
-    let mut g3: GateAnd = GateAnd::new(id_gen.get_next());
-    g3.children.add(&c5);
-    g3.children.add(&c6);
-
-    ft.add_element(Box::new(c1));
-    ft.add_element(Box::new(c2));
-    ft.add_element(Box::new(c3));
-    ft.add_element(Box::new(c4));
-    ft.add_element(Box::new(c5));
-    ft.add_element(Box::new(c6));
-
-    let mut g4: GateVote = GateVote::new(id_gen.get_next());
-    g4.children.add(&g1);
-    g4.children.add(&g2);
-    g4.children.add(&g3);
+// Ordered by time only, so `EventTime`s can live in a `BinaryHeap` as a
+// min-heap scheduler (via `Reverse`) instead of a linearly-scanned Vec.
+impl PartialEq for EventTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+    }
+}
 
-    ft.add_element(Box::new(g1));
-    ft.add_element(Box::new(g2));
-    ft.add_element(Box::new(g3));
+impl Eq for EventTime {}
 
-    let root_id = g4.get_id();
-    ft.add_element(Box::new(g4));
+impl PartialOrd for EventTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-    let basic_events = ft.get_basic_events();
-    let mut rng = rand::thread_rng();
+impl Ord for EventTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.time.partial_cmp(&other.time).unwrap_or(Ordering::Equal)
+    }
+}
 
-    let mut out_string = String::new();
+// Crude Monte Carlo samples every basic event from its true distribution.
+// ImportanceSampling inflates basic-event failure rates by `bias_factor` so
+// rare system failures show up far more often, at the cost of each trial
+// needing a likelihood-ratio weight to stay unbiased - see
+// `FT::sample_failure_weighted` and `BasicEvent::sample_failure_biased`.
+#[derive(Clone, Copy)]
+pub enum SamplingScheme {
+    Crude,
+    ImportanceSampling{bias_factor: f64},
+}
 
-    for _ in 0..10000 {
-        let mut event_times: Vec<EventTime> = Vec::new();
-
-        for element in &basic_events {
-            let element = *element;
-            let failure_time = ft.sample_failure(element, &mut rng).unwrap();
-            let event_time = EventTime{time: failure_time, element, event_type: EVENT_FAILURE};
-            let mut index = 0;
-            while index < event_times.len() && failure_time > event_times.get(index).unwrap().time {
-                index += 1;
-            };
-            event_times.insert(index, event_time);
+impl FT {
+    // Runs one trial to completion: seeds the heap with every basic event's
+    // first sampled failure, then repeatedly pops the earliest event,
+    // applies it, and schedules the resulting repair/failure. Returns the
+    // time the root element failed and the trial's likelihood-ratio weight
+    // (1.0 under `SamplingScheme::Crude`). Each step is O(log n) instead of
+    // the O(n) linear-scan insertion this replaces, which matters once the
+    // dynamic gates above are generating many more events per trial.
+    fn run_trial(&mut self, rng: &mut ThreadRng, scheme: SamplingScheme) -> (f64, f64) {
+        let mut event_times: BinaryHeap<Reverse<EventTime>> = BinaryHeap::new();
+        let mut weight = 1.0;
 
-        };
+        for element in self.get_basic_events() {
+            let (failure_time, sample_weight) = self.sample_failure_weighted(element, rng, scheme).unwrap();
+            weight *= sample_weight;
+            event_times.push(Reverse(EventTime{time: failure_time, element, event_type: EVENT_FAILURE}));
+        }
 
         loop {
-            let next_event_time = event_times.remove(0);
+            let Reverse(next_event_time) = event_times.pop().unwrap();
             let time = next_event_time.time;
             let element = next_event_time.element;
             let event_type = next_event_time.event_type;
-            //println!("Component {} failed at t = {}", element, time);
-            ft.process_event_time(next_event_time);
-            
+            self.process_event_time(next_event_time);
+
             if event_type == EVENT_FAILURE {
-                if ft.get_failed(root_id)  {
-                    //println!("System failed at t = {}", time);
-                    print!("{} ", time);
-                    out_string.push_str(&time.to_string());
-                    out_string.push(' ');
-                    break;
-                } else {
-                    let repair_interval = ft.sample_repair(element, &mut rng).unwrap();
+                if self.get_failed(self.root) {
+                    return (time, weight);
+                }
+                let repair_interval = self.sample_repair(element, rng).unwrap();
+                if repair_interval > 0.0 {
                     let repair_time = time + repair_interval;
-                    if repair_interval > 0.0 {
-                        let event_time = EventTime{time: repair_time, element, event_type: EVENT_REPAIR};
-                        let mut index = 0;
-                        while index < event_times.len() && repair_time > event_times.get(index).unwrap().time {
-                            index += 1;
-                        };
-                        event_times.insert(index, event_time);
-                    }
+                    event_times.push(Reverse(EventTime{time: repair_time, element, event_type: EVENT_REPAIR}));
                 }
             } else if event_type == EVENT_REPAIR {
-                let failure_interval = ft.sample_failure(element, &mut rng).unwrap();
+                let (failure_interval, sample_weight) = self.sample_failure_weighted(element, rng, scheme).unwrap();
+                weight *= sample_weight;
                 let failure_time = time + failure_interval;
-                let event_time = EventTime{time: failure_time, element, event_type: EVENT_FAILURE};
-                let mut index = 0;
-                while index < event_times.len() && failure_time > event_times.get(index).unwrap().time {
-                    index += 1;
-                };
-                event_times.insert(index, event_time);
+                event_times.push(Reverse(EventTime{time: failure_time, element, event_type: EVENT_FAILURE}));
             }
         }
+    }
+}
 
-        ft.reset_basic_events()
-    }
-    let out_string = out_string.trim_end();
-    let path = Path::new("output.txt");
-    let mut file = File::create(&path).expect("File creation error");
-    file.write(out_string.as_bytes()).expect("Write error");
-    println!("done");
-  
-    // hot spare with switch
-    // if switch is failed, gate cannot access spares
-    // list of spares
-    // boolean if it has failed (must encode some data about failure, cant be inferred like logical gates)
-    // when switch/primary/spares gets repaired, must update hot spare
-
-    /*
-    spare gate {
-        children = list of children components (primary+spare) ordered by priority
-        available children = bitmap mapping children availability
-        switch = switch component
-        current = current component //when something fails, check if this has failed
-        hasfailed() {}
-        update(component, type) {
-            if type == failure {
-                if component == switch {
-                    remove all children from available
-                } else if component == current {
-
-                    next = first TRUE in available
-                    if next is null (no parts left) {
-                        current = null
-                        failed = true
-                    } else {
-                        current = next
-                        //tell everyone else that this spare part is in use, cannot be taken
-                    } else //component is in the queue of spares {
-                        pop component from available
-                    }
-                }
-            } else if type == repair {
-                if component == switch {
-                    add all children back IF THEY ARE ALIVE ONLY
-                } else //component should never be current, since current is alive {
-                    add component back to available IN CORRECT PRIORITY ORDER (how?) (maybe bitmap easier?)
-                }
-            }
+fn main() {
+    // The 6-basic-event 2-of-3-vote-of-AND-pairs system used to be hand-built
+    // here with IDGenerator/BasicEvent::new/children.add; it now lives in
+    // models/six_component.json and is loaded through the same FT::from_file
+    // path any other model would use, so a new model doesn't need a rebuild.
+    let mut ft = FT::from_file(Path::new("models/six_component.json")).expect("Could not load model");
+
+    match ft.minimal_cut_sets() {
+        Ok(cut_sets) => {
+            let named: Vec<Vec<String>> = cut_sets.iter()
+                .map(|set| set.iter().map(|&id| ft.name_of(id)).collect())
+                .collect();
+            println!("Minimal cut sets: {:?}", named);
         }
-    }*/
+        Err(err) => println!("Minimal cut sets: {}", err),
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut estimator = Estimator::new();
+
+    ft.reset_elements();
+    for _ in 0..10000 {
+        let (time, weight) = ft.run_trial(&mut rng, SamplingScheme::Crude);
+        estimator.add_weighted_sample(time, weight);
+        ft.reset_elements();
+    }
+
+    let ci = estimator.confidence_interval().expect("10000 trials is well over the 2-batch minimum");
+    println!("MTTF: {} (95% CI [{}, {}], se = {})", estimator.mttf(), ci.lower, ci.upper, estimator.standard_error());
+    for t in [50.0, 100.0, 200.0, 400.0] {
+        println!("F({}) = {}", t, estimator.unreliability_at(t));
+    }
+
+    // Rare-event check: a non-repairable 2-of-3 vote, run once under crude
+    // sampling and once under importance sampling, to confirm both schemes
+    // agree within their confidence intervals. A mild bias factor is used
+    // deliberately: over-tilting the sampling distribution makes individual
+    // trial weights wildly unequal, which blows up the variance of the
+    // importance-sampling estimate even though it stays unbiased in theory.
+    for (label, scheme) in [("crude", SamplingScheme::Crude), ("importance sampling", SamplingScheme::ImportanceSampling{bias_factor: 2.0})] {
+        let mut two_of_three = build_two_of_three();
+        let mut estimator = Estimator::new();
+        two_of_three.reset_elements();
+        for _ in 0..10000 {
+            let (time, weight) = two_of_three.run_trial(&mut rng, scheme);
+            estimator.add_weighted_sample(time, weight);
+            two_of_three.reset_elements();
+        }
+        let ci = estimator.confidence_interval().expect("10000 trials is well over the 2-batch minimum");
+        println!("[2-of-3, {}] MTTF: {} (95% CI [{}, {}]), F(20) = {}", label, estimator.mttf(), ci.lower, ci.upper, estimator.unreliability_at(20.0));
+    }
+
+    // Dynamic-gate demonstration: a hot-spare pool whose only member is also
+    // an FDEP dependent, so a trigger failure force-kills it outright rather
+    // than it failing on its own schedule. Confirms force_dead's forced death
+    // is actually seen by the gate watching that element.
+    let mut spare_system = build_fdep_spare_demo();
+    spare_system.reset_elements();
+    let (time, _) = spare_system.run_trial(&mut rng, SamplingScheme::Crude);
+    println!("[FDEP+SPARE] system failed at t = {} (failed: {})", time, spare_system.get_failed(spare_system.root));
+
+    // A GatePAND (ordered AND) and a GateSEQ (ordered pool) combined under a
+    // plain OR, to demonstrate both outside the spare/FDEP scenario above.
+    let mut pand_seq_system = build_pand_seq_demo();
+    pand_seq_system.reset_elements();
+    let (time, _) = pand_seq_system.run_trial(&mut rng, SamplingScheme::Crude);
+    println!("[PAND+SEQ] system failed at t = {} (failed: {})", time, pand_seq_system.get_failed(pand_seq_system.root));
+}
+
+// A non-repairable 2-of-3 vote over identical exponential basic events,
+// used to sanity-check importance sampling against crude Monte Carlo.
+fn build_two_of_three() -> FT {
+    let mut id_gen = IDGenerator::new();
+    let mut ft = FT::new();
+
+    let c1: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_NONE, 0.0, 0.0);
+    let c2: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_NONE, 0.0, 0.0);
+    let c3: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_NONE, 0.0, 0.0);
+
+    let mut vote: GateVote = GateVote::new(id_gen.get_next(), 2);
+    vote.children.add(&c1);
+    vote.children.add(&c2);
+    vote.children.add(&c3);
+
+    ft.add_element(Box::new(c1));
+    ft.add_element(Box::new(c2));
+    ft.add_element(Box::new(c3));
+
+    ft.root = vote.get_id();
+    ft.add_element(Box::new(vote));
+
+    ft
+}
+
+// A hot-spare pool whose only member is also the dependent of an FDEP: the
+// trigger failing forces the dependent dead directly, without it ever
+// sampling its own failure. Exercises force_dead's notify_dynamic_gates path,
+// since the spare only learns its active child died through that route.
+fn build_fdep_spare_demo() -> FT {
+    let mut id_gen = IDGenerator::new();
+    let mut ft = FT::new();
+
+    let trigger: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/10.0, 0.0, DIST_NONE, 0.0, 0.0);
+    let dependent: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/10000.0, 0.0, DIST_NONE, 0.0, 0.0);
+    let switch: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/10000.0, 0.0, DIST_NONE, 0.0, 0.0);
+
+    let mut fdep = GateFDEP::new(id_gen.get_next(), trigger.get_id());
+    fdep.dependents.add_id(dependent.get_id());
+
+    let mut spare = GateSpare::new(id_gen.get_next(), switch.get_id());
+    spare.children.add_id(dependent.get_id());
+
+    ft.add_element(Box::new(trigger));
+    ft.add_element(Box::new(dependent));
+    ft.add_element(Box::new(switch));
+    ft.add_element(Box::new(fdep));
+
+    ft.root = spare.get_id();
+    ft.add_element(Box::new(spare));
+
+    ft
+}
+
+// A GatePAND over two children and a GateSEQ over two different children,
+// combined under an OR so either one failing ends the trial. The children
+// are repairable, since GatePAND re-evaluates ordering on every failure and
+// an out-of-order draw would otherwise leave it permanently unresolved; a
+// GateSEQ violation is in fact permanent by design (see its own doc comment),
+// so the OR is what keeps this demo from hanging if that gate gets a
+// disqualifying draw - the PAND side can still go on to resolve the trial.
+fn build_pand_seq_demo() -> FT {
+    let mut id_gen = IDGenerator::new();
+    let mut ft = FT::new();
+
+    let p1: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_EXP, 1.0/20.0, 0.0);
+    let p2: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_EXP, 1.0/20.0, 0.0);
+    let s1: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_EXP, 1.0/20.0, 0.0);
+    let s2: BasicEvent = BasicEvent::new(id_gen.get_next(), DIST_EXP, 1.0/100.0, 0.0, DIST_EXP, 1.0/20.0, 0.0);
+
+    let mut pand = GatePAND::new(id_gen.get_next());
+    pand.children.add_id(p1.get_id());
+    pand.children.add_id(p2.get_id());
+
+    let mut seq = GateSEQ::new(id_gen.get_next());
+    seq.children.add_id(s1.get_id());
+    seq.children.add_id(s2.get_id());
+
+    let mut or_gate = GateOr::new(id_gen.get_next());
+
+    ft.add_element(Box::new(p1));
+    ft.add_element(Box::new(p2));
+    ft.add_element(Box::new(s1));
+    ft.add_element(Box::new(s2));
+
+    or_gate.children.add_id(pand.get_id());
+    or_gate.children.add_id(seq.get_id());
+
+    ft.add_element(Box::new(pand));
+    ft.add_element(Box::new(seq));
+
+    ft.root = or_gate.get_id();
+    ft.add_element(Box::new(or_gate));
+
+    ft
 }
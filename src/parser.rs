@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{BasicEvent, FTElement, GateAnd, GateOr, GateSpare, GateVote, FT,
+    DIST_EXP, DIST_GAMMA, DIST_NONE, DIST_WEIBULL};
+
+#[derive(Deserialize)]
+struct ModelFile {
+    root: String,
+    elements: HashMap<String, ElementSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ElementSpec {
+    Basic{failure: DistributionSpec, #[serde(default)] repair: DistributionSpec},
+    And{children: Vec<String>},
+    Or{children: Vec<String>},
+    // `threshold` is the minimum number of failed children for the gate to
+    // fail; defaults to a simple majority (more than half) when omitted.
+    Vote{#[serde(default)] threshold: Option<usize>, children: Vec<String>},
+    Spare{switch: String, children: Vec<String>},
+}
+
+#[derive(Deserialize)]
+struct DistributionSpec {
+    #[serde(default = "default_dist")]
+    dist: String,
+    #[serde(default)]
+    scale: f64,
+    #[serde(default)]
+    shape: f64,
+}
+
+impl Default for DistributionSpec {
+    fn default() -> DistributionSpec {
+        DistributionSpec{dist: default_dist(), scale: 0.0, shape: 0.0}
+    }
+}
+
+fn default_dist() -> String {
+    "none".to_string()
+}
+
+fn dist_id(name: &str) -> Result<usize, String> {
+    match name {
+        "exp" => Ok(DIST_EXP),
+        "weibull" => Ok(DIST_WEIBULL),
+        "gamma" => Ok(DIST_GAMMA),
+        "none" => Ok(DIST_NONE),
+        other => Err(format!("Unknown distribution type '{}'", other)),
+    }
+}
+
+fn children_of(spec: &ElementSpec) -> Vec<&String> {
+    match spec {
+        ElementSpec::Basic{..} => Vec::new(),
+        ElementSpec::And{children} | ElementSpec::Or{children} | ElementSpec::Vote{children, ..} => children.iter().collect(),
+        ElementSpec::Spare{switch, children} => {
+            let mut referenced: Vec<&String> = children.iter().collect();
+            referenced.push(switch);
+            referenced
+        }
+    }
+}
+
+// Builds an `FT` from a declarative JSON model: a map of element names to
+// their type (basic/and/or/vote/spare), distribution parameters, and child
+// references by name. Names are resolved to the dense integer ids the
+// engine works with, and the graph is validated - every referenced child
+// must exist and the result must be acyclic - before any element is built.
+pub fn parse(path: &Path) -> Result<FT, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    let model: ModelFile = serde_json::from_str(&text).map_err(|err| format!("Invalid model file: {}", err))?;
+
+    for (name, spec) in &model.elements {
+        for child in children_of(spec) {
+            if !model.elements.contains_key(child) {
+                return Err(format!("Element '{}' references undefined element '{}'", name, child));
+            }
+        }
+    }
+    check_acyclic(&model)?;
+
+    let ids: HashMap<String, usize> = model.elements.keys().enumerate().map(|(id, name)| (name.clone(), id)).collect();
+    let resolve = |name: &str| -> Result<usize, String> {
+        ids.get(name).copied().ok_or_else(|| format!("Reference to undefined element '{}'", name))
+    };
+
+    let mut ft = FT::new();
+    for (name, spec) in &model.elements {
+        let id = ids[name];
+        let element: Box<dyn FTElement> = match spec {
+            ElementSpec::Basic{failure, repair} => Box::new(BasicEvent::new(id,
+                dist_id(&failure.dist)?, failure.scale, failure.shape,
+                dist_id(&repair.dist)?, repair.scale, repair.shape)),
+            ElementSpec::And{children} => {
+                let mut gate = GateAnd::new(id);
+                for child in children {
+                    gate.children.add_id(resolve(child)?);
+                }
+                Box::new(gate)
+            }
+            ElementSpec::Or{children} => {
+                let mut gate = GateOr::new(id);
+                for child in children {
+                    gate.children.add_id(resolve(child)?);
+                }
+                Box::new(gate)
+            }
+            ElementSpec::Vote{threshold, children} => {
+                let threshold = threshold.unwrap_or(children.len() / 2 + 1);
+                let mut gate = GateVote::new(id, threshold);
+                for child in children {
+                    gate.children.add_id(resolve(child)?);
+                }
+                Box::new(gate)
+            }
+            ElementSpec::Spare{switch, children} => {
+                let mut gate = GateSpare::new(id, resolve(switch)?);
+                for child in children {
+                    gate.children.add_id(resolve(child)?);
+                }
+                Box::new(gate)
+            }
+        };
+        ft.add_element(element);
+        ft.set_name(id, name.clone());
+    }
+
+    ft.root = resolve(&model.root)?;
+    Ok(ft)
+}
+
+// DFS with the usual white/gray/black coloring; a back-edge to a gray node
+// means the graph is cyclic.
+fn check_acyclic(model: &ModelFile) -> Result<(), String> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Color { Gray, Black }
+
+    fn visit<'a>(name: &'a str, model: &'a ModelFile, colors: &mut HashMap<&'a str, Color>) -> Result<(), String> {
+        match colors.get(name) {
+            Some(Color::Black) => return Ok(()),
+            Some(Color::Gray) => return Err(format!("Cycle detected at element '{}'", name)),
+            _ => {}
+        }
+        colors.insert(name, Color::Gray);
+        if let Some(spec) = model.elements.get(name) {
+            for child in children_of(spec) {
+                visit(child, model, colors)?;
+            }
+        }
+        colors.insert(name, Color::Black);
+        Ok(())
+    }
+
+    let mut colors: HashMap<&str, Color> = HashMap::new();
+    for name in model.elements.keys() {
+        visit(name, model, &mut colors)?;
+    }
+    Ok(())
+}